@@ -4,9 +4,10 @@
 //!
 //! `scanpw` provides a macro and a function (for more granular error handling) to
 //! facilitate reading passwords from standard input in a secure manner. It expands
-//! to an expression that returns a [`String`], so it can be assigned to
-//! a variable or used directly. The macro may take arguments like those to
-//! [`print`], which can be used to generate a prompt.
+//! to an expression that returns a [`SecretPassword`], so it can be assigned to
+//! a variable or used directly (it derefs to `&str`, and [`SecretPassword::into_string`]
+//! is available for callers that need an owned [`String`]). The macro may take
+//! arguments like those to [`print`], which can be used to generate a prompt.
 //!
 //! # Examples
 //!
@@ -55,19 +56,61 @@
 //! ```
 //!
 //! The default behavior is to echo `*`s for each character entered.
+//!
+//! ## Writing the prompt somewhere other than stderr
+//!
+//! [`scanpw`] prints the prompt, and [`try_scanpw`] its echoed characters, to
+//! `stderr` (so they aren't captured if a caller pipes `stdout`). To send it elsewhere, or to
+//! otherwise customize the reader, use [`Scanpw`] directly:
+//!
+//! ```no_run
+//! # use scanpw::Scanpw;
+//! let password = Scanpw::new().echo(Some('*')).read().unwrap();
+//! ```
+//!
+//! ## Confirming a new password
+//!
+//! `scanpw!(confirm: retries, ...)` reads a password, reads it again to
+//! confirm, and retries (reprompting both times) up to `retries` times if the
+//! two entries don't match:
+//!
+//! ```no_run
+//! # #[macro_use] extern crate scanpw;
+//! let password = scanpw!(confirm: 2, "New password: ", "Confirm password: ");
+//! ```
+//!
+//! ## Rejecting weak passwords
+//!
+//! `scanpw!(validate: validator, ...)` attaches a validator (see the
+//! [`validators`] module for ready-made ones) that's checked when the user
+//! presses Enter; if it rejects the entry, the message is printed, the entry
+//! is cleared, and the user tries again:
+//!
+//! ```no_run
+//! # #[macro_use] extern crate scanpw;
+//! use scanpw::validators::min_length;
+//!
+//! let password = scanpw!(validate: min_length(8), "New password: ");
+//! ```
 
+mod scanpw;
+mod secret;
 mod try_scanpw;
+pub mod validators;
 
-pub use try_scanpw::try_scanpw;
+pub use scanpw::Scanpw;
+pub use secret::{passwords_match, SecretPassword};
+pub use try_scanpw::{try_scanpw, try_scanpw_confirm, try_scanpw_confirm_with};
 
 /// Reads a password from standard input
 ///
-/// Invocations of [`scanpw`] expand to an expression retuning a [`String`] that
-/// contains a line of input from `stdin`. It can be invoked with arguments
+/// Invocations of [`scanpw`] expand to an expression retuning a [`SecretPassword`]
+/// that contains a line of input from `stdin`. It can be invoked with arguments
 /// identical to those of [`print`], and if so, those arguments will be used
-/// to generate a prompt on the standard output. Input will begin on the same
-/// line that the prompt ends, if any. If no arguments are provided, input will
-/// start where the cursor is, which is likely to be on its own empty line.
+/// to generate a prompt on standard error (so it isn't captured if a caller
+/// pipes `stdout`). Input will begin on the same line that the prompt ends,
+/// if any. If no arguments are provided, input will start where the cursor
+/// is, which is likely to be on its own empty line.
 ///
 /// # Panics
 ///
@@ -81,18 +124,18 @@ pub use try_scanpw::try_scanpw;
 
     // Prompt, echo '*'s
     ( $fmt:literal ) => {{
-        print!($fmt);
+        eprint!($fmt);
         use ::std::io::Write;
-        ::std::io::stdout().flush().unwrap();
+        ::std::io::stderr().flush().unwrap();
 
         $crate::try_scanpw(Some('*')).unwrap()
     }};
 
     // Formatted prompt, echo '*'s
     ( $fmt:literal, $($args:tt)* ) => {{
-        print!("{}", format_args!($fmt, $($args)*));
+        eprint!("{}", format_args!($fmt, $($args)*));
         use ::std::io::Write;
-        ::std::io::stdout().flush().unwrap();
+        ::std::io::stderr().flush().unwrap();
 
         $crate::try_scanpw(Some('*')).unwrap()
     }};
@@ -104,19 +147,56 @@ pub use try_scanpw::try_scanpw;
 
     // Manually set echo mode, with prompt
     ( $echo:expr, $fmt:literal ) => {{
-        print!($fmt);
+        eprint!($fmt);
         use ::std::io::Write;
-        ::std::io::stdout().flush().unwrap();
+        ::std::io::stderr().flush().unwrap();
 
         $crate::try_scanpw($echo).unwrap()
     }};
 
     // Manually set echo mode, with formatted prompt
     ( $echo:expr, $fmt:literal, $($args:tt)* ) => {{
-        print!("{}", format_args!($fmt, $($args)*));
+        eprint!("{}", format_args!($fmt, $($args)*));
         use ::std::io::Write;
-        ::std::io::stdout().flush().unwrap();
+        ::std::io::stderr().flush().unwrap();
 
         $crate::try_scanpw($echo).unwrap()
     }};
+
+    // Confirmation prompt: read twice, echo '*'s, retry on mismatch
+    ( confirm: $retries:expr, $fmt1:literal, $fmt2:literal ) => {{
+        use ::std::io::Write;
+
+        $crate::try_scanpw_confirm_with(
+            Some('*'),
+            $retries,
+            || {
+                eprint!($fmt1);
+                ::std::io::stderr().flush().unwrap();
+            },
+            || {
+                eprint!($fmt2);
+                ::std::io::stderr().flush().unwrap();
+            },
+        )
+        .unwrap()
+    }};
+
+    // Prompt with a validator attached, echo '*'s
+    ( validate: $validator:expr, $fmt:literal ) => {{
+        eprint!($fmt);
+        use ::std::io::Write;
+        ::std::io::stderr().flush().unwrap();
+
+        $crate::Scanpw::new().validator($validator).read().unwrap()
+    }};
+
+    // Formatted prompt with a validator attached, echo '*'s
+    ( validate: $validator:expr, $fmt:literal, $($args:tt)* ) => {{
+        eprint!("{}", format_args!($fmt, $($args)*));
+        use ::std::io::Write;
+        ::std::io::stderr().flush().unwrap();
+
+        $crate::Scanpw::new().validator($validator).read().unwrap()
+    }};
 }
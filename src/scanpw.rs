@@ -0,0 +1,332 @@
+use std::io::{self, stderr, stdin, BufRead, Stderr, Write};
+
+use crossterm::{
+    cursor::{position, MoveLeft, MoveToNextLine},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    execute,
+    style::Print,
+    terminal,
+    tty::IsTty,
+};
+
+use crate::secret::{zero_string, zero_tail, SecretPassword};
+
+/// A hook that inspects a candidate password when the user presses Enter.
+/// `Err(msg)` rejects the entry: `msg` is printed and the current entry is
+/// cleared so the user can try again, instead of `read` returning.
+type Validator = Box<dyn FnMut(&str) -> Result<(), String>>;
+
+/// A source of terminal events for `Scanpw::read` to consume one at a time.
+/// Defaults to [`event::read`], which blocks on the process's controlling
+/// terminal; tests can supply a different source to script input without a
+/// real terminal.
+type EventSource = Box<dyn FnMut() -> crossterm::Result<Event>>;
+
+/// A reasonable starting capacity for the password buffer, to avoid the
+/// reallocation-scatter that would otherwise leave copies of the partial
+/// password behind in memory as it grows.
+const INITIAL_CAPACITY: usize = 32;
+
+/// A configurable password reader
+///
+/// `Scanpw` owns the writer that echoed characters are printed to, decoupling
+/// the prompt from `stdin`/`stdout`. By default it writes to `stderr` (so the
+/// prompt isn't captured when a caller pipes `stdout`) and echoes `*`s; call
+/// [`writer`](Scanpw::writer) or [`echo`](Scanpw::echo) to override either,
+/// optionally attach a [`validator`](Scanpw::validator), then
+/// [`read`](Scanpw::read) to get the password.
+///
+/// Key events are read one at a time from an [`EventSource`] that defaults to
+/// crossterm's [`event::read`] on the terminal; call
+/// [`events`](Scanpw::events) to supply scripted key and paste events
+/// instead. A custom source does not replace the terminal itself: `read`
+/// still checks whether `stdin` is a terminal, enables raw mode and queries
+/// the cursor position on it.
+///
+/// [`try_scanpw`](crate::try_scanpw) and [`scanpw!`](crate::scanpw) are thin
+/// wrappers over `Scanpw` with its default settings.
+pub struct Scanpw<W: Write> {
+    writer: W,
+    echo: Option<char>,
+    validator: Option<Validator>,
+    events: EventSource,
+}
+
+impl Scanpw<Stderr> {
+    /// Creates a new `Scanpw` that writes to `stderr` and echoes `*`s.
+    pub fn new() -> Self {
+        Scanpw {
+            writer: stderr(),
+            echo: Some('*'),
+            validator: None,
+            events: Box::new(event::read),
+        }
+    }
+}
+
+impl Default for Scanpw<Stderr> {
+    fn default() -> Self {
+        Scanpw::new()
+    }
+}
+
+impl<W: Write> Scanpw<W> {
+    /// Sets the writer that echoed characters (and the prompt, if the caller
+    /// prints one through it) are sent to.
+    pub fn writer<W2: Write>(self, writer: W2) -> Scanpw<W2> {
+        Scanpw {
+            writer,
+            echo: self.echo,
+            validator: self.validator,
+            events: self.events,
+        }
+    }
+
+    /// Sets the echo behavior: `Some(c)` prints `c` for each character
+    /// entered, `None` prints the entered characters themselves.
+    pub fn echo(mut self, echo: Option<char>) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Sets a validator that's run against the entry when the user presses
+    /// Enter. If it returns `Err(msg)`, `msg` is printed, the entry is
+    /// cleared, and the user is prompted to try again instead of `read`
+    /// returning. See the [`validators`](crate::validators) module for
+    /// ready-made validators.
+    pub fn validator<F>(mut self, validator: F) -> Self
+    where
+        F: FnMut(&str) -> Result<(), String> + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Sets the source `read` consumes events from, one at a time. Defaults
+    /// to [`event::read`], which blocks on the process's controlling
+    /// terminal. Only the events come from this source; raw mode and cursor
+    /// position still use the real terminal, and when `stdin` is not a
+    /// terminal the source is not used at all.
+    pub fn events<F>(mut self, events: F) -> Self
+    where
+        F: FnMut() -> crossterm::Result<Event> + 'static,
+    {
+        self.events = Box::new(events);
+        self
+    }
+
+    /// Reads a password, returning a [`SecretPassword`].
+    ///
+    /// If standard input isn't a terminal (for example, it's piped or
+    /// redirected from a file), raw mode and echo handling are skipped
+    /// entirely in favor of reading a single line directly from `stdin`, so
+    /// `Scanpw` remains usable in scripts and CI. A [`validator`](Scanpw::validator)
+    /// is still applied in that case, but since there's no user to reprompt,
+    /// a rejected entry is returned as an error instead of retried.
+    pub fn read(mut self) -> crossterm::Result<SecretPassword> {
+        if !stdin().is_tty() {
+            return read_line(self.validator.as_mut());
+        }
+
+        // Enter raw mode so we can control character echoing
+        terminal::enable_raw_mode()?;
+
+        // Enable bracketed paste so pasted text arrives as a single
+        // `Event::Paste` instead of being dropped or mangled as a burst of
+        // `Event::Key`s
+        execute!(self.writer, event::EnableBracketedPaste)?;
+
+        // In case anything was printed prior to the beginning of the input on
+        // the same line, store the column the cursor started at. Updated
+        // after a failed validation, since the entry is cleared back to a
+        // fresh line.
+        let (mut max_left, _height) = position()?;
+
+        // The password
+        let mut pw = String::with_capacity(INITIAL_CAPACITY);
+
+        loop {
+            match (self.events)()? {
+                // A burst of pasted text
+                Event::Paste(mut data) => {
+                    for c in data.chars() {
+                        let c = self.echo.unwrap_or(c);
+                        execute!(self.writer, Print(c))?;
+                    }
+
+                    // Add the pasted text to the password, then zero crossterm's
+                    // copy so the plaintext doesn't linger in freed heap memory
+                    pw.push_str(&data);
+                    zero_string(&mut data);
+                }
+
+                Event::Key(k) => match k {
+                    // Normal character input
+                    KeyEvent {
+                        code: KeyCode::Char(c),
+                        modifiers,
+                        ..
+                    } if modifiers.is_empty() => {
+                        execute!(self.writer, Print(self.echo.unwrap_or(c)))?;
+
+                        // Add the character to the password
+                        pw.push(c);
+                    }
+
+                    // Password input completed, unless a validator rejects it
+                    KeyEvent {
+                        code: KeyCode::Enter,
+                        ..
+                    } => {
+                        if let Some(validator) = self.validator.as_mut() {
+                            if let Err(msg) = validator(&pw) {
+                                // Erase the echoed entry back to where input
+                                // started
+                                let (cur_left, _height) = position()?;
+                                for _ in 0..cur_left.saturating_sub(max_left) {
+                                    execute!(self.writer, MoveLeft(1), Print(" "), MoveLeft(1))?;
+                                }
+
+                                zero_string(&mut pw);
+                                pw.clear();
+
+                                execute!(self.writer, Print('\n'))?;
+                                execute!(self.writer, MoveToNextLine(1))?;
+                                writeln!(self.writer, "{}", msg)?;
+                                self.writer.flush()?;
+
+                                max_left = position()?.0;
+                                continue;
+                            }
+                        }
+
+                        execute!(self.writer, Print('\n'))?;
+                        execute!(self.writer, MoveToNextLine(1))?;
+                        break;
+                    }
+
+                    // Handle backspace
+                    KeyEvent {
+                        code: KeyCode::Backspace,
+                        ..
+                    } => {
+                        // If echo characters are enabled and any exist, remove
+                        // the rightmost one
+                        let (cur_left, _height) = position()?;
+
+                        // True if the next position isn't past the left of
+                        // the column where the cursor started
+                        let not_too_far = cur_left
+                            .checked_sub(1)
+                            .map(|np| np >= max_left)
+                            .unwrap_or(false);
+
+                        if not_too_far {
+                            execute!(self.writer, MoveLeft(1), Print(" "), MoveLeft(1))?;
+                        }
+
+                        // Delete the character from the password, zeroing the
+                        // bytes it occupied before truncating
+                        if let Some(c) = pw.pop() {
+                            zero_tail(&mut pw, c.len_utf8());
+                        }
+                    }
+
+                    // Pass Ctrl+C through as a signal like normal
+                    KeyEvent {
+                        code: KeyCode::Char('c'),
+                        modifiers,
+                        ..
+                    } if modifiers == KeyModifiers::CONTROL => {
+                        // This is a bit silly
+                        execute!(self.writer, Print("^C"),)?;
+
+                        // Zero what's been typed so far before abandoning it;
+                        // SIGINT's default disposition terminates the process
+                        // without running `Drop`, so this is the only chance
+                        zero_string(&mut pw);
+
+                        // Reset the terminal back to normal and exit
+                        execute!(self.writer, event::DisableBracketedPaste)?;
+                        terminal::disable_raw_mode()?;
+
+                        die();
+                    }
+
+                    // Ignore other cases
+                    _ => (),
+                },
+
+                // Ignore other events (resize, focus, mouse, etc)
+                _ => (),
+            }
+        }
+
+        // Disable bracketed paste before restoring the terminal
+        execute!(self.writer, event::DisableBracketedPaste)?;
+
+        // Reset the terminal back to normal
+        terminal::disable_raw_mode()?;
+
+        Ok(SecretPassword::new(pw))
+    }
+}
+
+/// Reads a single line from standard input without touching raw mode or echo,
+/// for use when stdin isn't a terminal (piped input, redirected files, etc).
+///
+/// If a validator is attached, it's applied once: there's no interactive user
+/// to reprompt here, so a rejected entry is a hard error instead of a retry,
+/// rather than silently letting a piped password through unchecked.
+fn read_line(validator: Option<&mut Validator>) -> crossterm::Result<SecretPassword> {
+    let mut pw = String::with_capacity(INITIAL_CAPACITY);
+    stdin().lock().read_line(&mut pw)?;
+
+    // Strip the trailing newline, and a preceding carriage return if present
+    if pw.ends_with('\n') {
+        pw.pop();
+        if pw.ends_with('\r') {
+            pw.pop();
+        }
+    }
+
+    if let Some(validator) = validator {
+        if let Err(msg) = validator(&pw) {
+            zero_string(&mut pw);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        }
+    }
+
+    Ok(SecretPassword::new(pw))
+}
+
+fn die() {
+    cfg_if::cfg_if! {
+        if #[cfg(unix)] {
+            use nix::sys::signal::{raise, Signal::SIGINT};
+
+            raise(SIGINT).unwrap();
+        } else if #[cfg(windows)] {
+            use winapi::um::{
+                wincon::{
+                    GenerateConsoleCtrlEvent,
+                    CTRL_C_EVENT,
+                },
+                processthreadsapi::GetCurrentProcessId,
+            };
+
+            unsafe {
+                let res = GenerateConsoleCtrlEvent(
+                    CTRL_C_EVENT,
+                    GetCurrentProcessId(),
+                );
+
+                if res == 0 {
+                    panic!("failed to generate CTRL_C_EVENT");
+                }
+            }
+        } else {
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+use std::ops::Deref;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+/// A password read from standard input whose backing buffer is zeroed on drop
+///
+/// [`try_scanpw`](crate::try_scanpw) and [`scanpw!`](crate::scanpw) return this
+/// type instead of a plain [`String`] so the secret doesn't linger in freed
+/// heap memory (or in the memory left behind by a `String`'s reallocations).
+/// It derefs to `&str` for ergonomic use, and
+/// [`into_string`](SecretPassword::into_string) is available for callers that
+/// need to move the plaintext out and are willing to take over responsibility
+/// for zeroing it themselves.
+pub struct SecretPassword(String);
+
+impl SecretPassword {
+    pub(crate) fn new(pw: String) -> Self {
+        SecretPassword(pw)
+    }
+
+    /// Consumes the `SecretPassword`, returning the inner `String`.
+    ///
+    /// The returned `String` is *not* zeroed on drop. Prefer using the
+    /// `SecretPassword` directly (it derefs to `&str`) whenever possible.
+    pub fn into_string(mut self) -> String {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl Deref for SecretPassword {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SecretPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for SecretPassword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretPassword(...)")
+    }
+}
+
+impl Drop for SecretPassword {
+    fn drop(&mut self) {
+        zero_string(&mut self.0);
+    }
+}
+
+/// Overwrites every byte of `s`'s backing buffer with zeros using volatile
+/// writes (followed by a compiler fence), so the optimizer can't elide the
+/// write just because `s` is about to be dropped or truncated.
+pub(crate) fn zero_string(s: &mut String) {
+    unsafe {
+        for b in s.as_mut_vec().iter_mut() {
+            std::ptr::write_volatile(b, 0);
+        }
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Zeros the `len` bytes immediately after the end of `s` (still within its
+/// capacity, after those bytes have been logically truncated away). Used to
+/// wipe the tail of the buffer left behind by `String::pop`.
+pub(crate) fn zero_tail(s: &mut String, len: usize) {
+    if len == 0 {
+        return;
+    }
+
+    unsafe {
+        let start = s.len();
+        let ptr = s.as_mut_vec().as_mut_ptr().add(start);
+        for i in 0..len {
+            std::ptr::write_volatile(ptr.add(i), 0);
+        }
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Compares two strings for equality without short-circuiting on the secret
+/// itself: every byte of both is inspected regardless of where they first
+/// differ, so neither an early return nor the time taken leaks how much of
+/// the two entries matched.
+pub fn passwords_match(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let max_len = a.len().max(b.len());
+
+    let mut diff = a.len() ^ b.len();
+    for i in 0..max_len {
+        let x = a.get(i).copied().unwrap_or(0) as usize;
+        let y = b.get(i).copied().unwrap_or(0) as usize;
+        diff |= x ^ y;
+    }
+
+    diff == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_passwords() {
+        assert!(passwords_match("hunter2", "hunter2"));
+        assert!(passwords_match("", ""));
+    }
+
+    #[test]
+    fn different_passwords() {
+        assert!(!passwords_match("hunter2", "hunter3"));
+    }
+
+    #[test]
+    fn different_lengths() {
+        assert!(!passwords_match("short", "shorter"));
+        assert!(!passwords_match("shorter", "short"));
+        assert!(!passwords_match("", "a"));
+    }
+
+    #[test]
+    fn shared_prefix_different_length() {
+        // Regression check: a naive byte-by-byte comparison that stops at
+        // `min_len` would call this a match.
+        assert!(!passwords_match("password", "passwords"));
+    }
+
+    #[test]
+    fn zero_string_clears_every_byte() {
+        let mut s = String::from("hunter2");
+        zero_string(&mut s);
+
+        // The buffer is mutated in place, so the zeroed bytes are directly
+        // observable through the safe API, no read-after-free needed.
+        assert!(s.as_bytes().iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn zero_tail_clears_the_bytes_left_behind_by_pop() {
+        let mut s = String::with_capacity(8);
+        s.push('a');
+        s.push('b');
+        s.pop();
+
+        // `pop` only shrinks the logical length; the byte it used to occupy
+        // is still sitting in the buffer just past the new end until
+        // `zero_tail` overwrites it.
+        let tail_ptr = unsafe { s.as_mut_vec().as_mut_ptr().add(s.len()) };
+        zero_tail(&mut s, 1);
+
+        assert_eq!(unsafe { *tail_ptr }, 0);
+    }
+}
@@ -1,133 +1,70 @@
-use std::io::{stdout, Write};
+use std::io;
 
-use crossterm::{
-    cursor::{position, MoveLeft, MoveToNextLine},
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
-    execute,
-    style::Print,
-    terminal,
+use crate::{
+    secret::{passwords_match, SecretPassword},
+    Scanpw,
 };
 
 /// Attempts to read a password from standard input
 ///
 /// `echo` controls whether a replacement character should be printed each time
 /// the user enters a character, and if so, which character. The result is
-/// either a [`String`] or a [`crossterm::ErrorKind`]. Input begins wherever the
-/// cursor was before calling this function, which is likely to be on its own
-/// empty line.
-pub fn try_scanpw(echo: Option<char>) -> crossterm::Result<String> {
-    // Enter raw mode so we can control character echoing
-    terminal::enable_raw_mode()?;
-
-    // In case anything was printed prior to the beginning of the input on the
-    // same line, store the column the cursor started at
-    let (max_left, _height) = position()?;
-
-    // The password
-    let mut pw = String::new();
-
-    loop {
-        if let Event::Key(k) = event::read()? {
-            match k {
-                // Normal character input
-                KeyEvent {
-                    code: KeyCode::Char(c),
-                    modifiers,
-                } if modifiers.is_empty() => {
-                    let c = echo.unwrap_or(c);
-                    execute!(stdout(), Print(c))?;
-
-                    // Add the character to the password
-                    pw.push(c);
-                }
-
-                // Password input completed
-                KeyEvent {
-                    code: KeyCode::Enter,
-                    ..
-                } => {
-                    execute!(stdout(), Print('\n'))?;
-                    execute!(stdout(), MoveToNextLine(1))?;
-                    break;
-                }
-
-                // Handle backspace
-                KeyEvent {
-                    code: KeyCode::Backspace,
-                    ..
-                } => {
-                    // If echo characters are enabled and any exist, remove the
-                    // rightmost one
-                    let (cur_left, _height) = position()?;
-
-                    // True if the next position isn't past the left of the
-                    // column where the cursor started
-                    let not_too_far = cur_left
-                        .checked_sub(1)
-                        .map(|np| np >= max_left)
-                        .unwrap_or(false);
-
-                    if not_too_far {
-                        execute!(stdout(), MoveLeft(1), Print(" "), MoveLeft(1))?;
-                    }
-
-                    // Delete the character from the password
-                    pw.pop();
-                }
-
-                // Pass Ctrl+C through as a signal like normal
-                KeyEvent {
-                    code: KeyCode::Char('c'),
-                    modifiers,
-                } if modifiers == KeyModifiers::CONTROL => {
-                    // This is a bit silly
-                    execute!(stdout(), Print("^C"),)?;
-
-                    // Reset the terminal back to normal and exit
-                    terminal::disable_raw_mode()?;
-
-                    die();
-                }
-
-                // Ignore other cases
-                _ => (),
-            }
-        }
-    }
-
-    // Reset the terminal back to normal
-    terminal::disable_raw_mode()?;
-
-    Ok(pw)
+/// either a [`SecretPassword`] or a [`crossterm::ErrorKind`]. Input begins
+/// wherever the cursor was before calling this function, which is likely to
+/// be on its own empty line.
+///
+/// This is a thin wrapper over [`Scanpw`] with its default settings; use
+/// `Scanpw` directly for control over where the prompt is written or where
+/// input is read from.
+pub fn try_scanpw(echo: Option<char>) -> crossterm::Result<SecretPassword> {
+    Scanpw::new().echo(echo).read()
 }
 
-fn die() {
-    cfg_if::cfg_if! {
-        if #[cfg(unix)] {
-            use nix::sys::signal::{raise, Signal::SIGINT};
-
-            raise(SIGINT).unwrap();
-        } else if #[cfg(windows)] {
-            use winapi::um::{
-                wincon::{
-                    GenerateConsoleCtrlEvent,
-                    CTRL_C_EVENT,
-                },
-                processthreadsapi::GetCurrentProcessId,
-            };
+/// Attempts to read a password twice and confirms the two entries match
+///
+/// `echo` is passed through to both reads. If the entries don't match, the
+/// first one is dropped (which zeroes it, courtesy of [`SecretPassword`]'s
+/// `Drop` impl) and both are read again, up to `retries` additional times,
+/// before giving up with an error. The comparison never short-circuits on the
+/// secret itself; see [`passwords_match`](crate::secret::passwords_match).
+///
+/// This is the same loop `scanpw!`'s `confirm:` form uses, but without a
+/// prompt printed between the two reads; prefer the macro when you want the
+/// user to see which entry is the confirmation.
+pub fn try_scanpw_confirm(echo: Option<char>, retries: usize) -> crossterm::Result<SecretPassword> {
+    try_scanpw_confirm_with(echo, retries, || {}, || {})
+}
 
-            unsafe {
-                let res = GenerateConsoleCtrlEvent(
-                    CTRL_C_EVENT,
-                    GetCurrentProcessId(),
-                );
+/// Like [`try_scanpw_confirm`], but calls `before_first`/`before_second`
+/// immediately before each of the two reads on every attempt.
+///
+/// This is what backs `scanpw!`'s `confirm:` form, which uses the hooks to
+/// print its two prompts, so the retry/zero/compare logic underneath only
+/// has to exist once.
+pub fn try_scanpw_confirm_with(
+    echo: Option<char>,
+    retries: usize,
+    mut before_first: impl FnMut(),
+    mut before_second: impl FnMut(),
+) -> crossterm::Result<SecretPassword> {
+    for attempt in 0..=retries {
+        before_first();
+        let first = try_scanpw(echo)?;
+
+        before_second();
+        let second = try_scanpw(echo)?;
+
+        if passwords_match(&first, &second) {
+            return Ok(first);
+        }
 
-                if res == 0 {
-                    panic!("failed to generate CTRL_C_EVENT");
-                }
-            }
-        } else {
-            std::process::exit(1);
+        if attempt < retries {
+            eprintln!("Passwords did not match, please try again.");
         }
     }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "password confirmation did not match",
+    ))
 }
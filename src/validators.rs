@@ -0,0 +1,167 @@
+//! Ready-made validators for [`Scanpw::validator`](crate::Scanpw::validator)
+//!
+//! Each function here returns a closure suitable for passing straight to
+//! `validator`, or to `scanpw!`'s `validate:` form.
+
+/// Rejects passwords with fewer than `min_len` characters.
+pub fn min_length(min_len: usize) -> impl FnMut(&str) -> Result<(), String> {
+    move |pw: &str| {
+        if pw.chars().count() < min_len {
+            Err(format!(
+                "Password must be at least {} characters long.",
+                min_len
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Rejects passwords that don't mix at least `min_classes` of the four
+/// character classes: lowercase letters, uppercase letters, digits, and
+/// everything else (symbols).
+pub fn min_character_classes(min_classes: usize) -> impl FnMut(&str) -> Result<(), String> {
+    move |pw: &str| {
+        let Classes { lower, upper, digit, symbol } = Classes::of(pw);
+        let count = [lower, upper, digit, symbol].into_iter().filter(|b| *b).count();
+
+        if count < min_classes {
+            Err(format!(
+                "Password must contain at least {} of: lowercase letters, \
+                 uppercase letters, digits, symbols.",
+                min_classes
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Rejects passwords with an estimated entropy below `min_bits`, computed as
+/// `len * log2(effective_alphabet_size)`, where the alphabet size is inferred
+/// from which character classes appear in the password.
+pub fn min_entropy_bits(min_bits: f64) -> impl FnMut(&str) -> Result<(), String> {
+    move |pw: &str| {
+        if entropy_bits(pw) < min_bits {
+            Err(format!(
+                "Password is too weak (needs roughly {:.0} bits of entropy).",
+                min_bits
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Which character classes appear in a password.
+struct Classes {
+    lower: bool,
+    upper: bool,
+    digit: bool,
+    symbol: bool,
+}
+
+impl Classes {
+    fn of(pw: &str) -> Self {
+        let mut classes = Classes {
+            lower: false,
+            upper: false,
+            digit: false,
+            symbol: false,
+        };
+
+        for c in pw.chars() {
+            if c.is_ascii_lowercase() {
+                classes.lower = true;
+            } else if c.is_ascii_uppercase() {
+                classes.upper = true;
+            } else if c.is_ascii_digit() {
+                classes.digit = true;
+            } else {
+                classes.symbol = true;
+            }
+        }
+
+        classes
+    }
+
+    /// The size of the character set these classes imply (26 lowercase, 26
+    /// uppercase, 10 digits, a rough estimate of 32 common symbols).
+    fn alphabet_size(&self) -> usize {
+        let mut size = 0;
+        if self.lower {
+            size += 26;
+        }
+        if self.upper {
+            size += 26;
+        }
+        if self.digit {
+            size += 10;
+        }
+        if self.symbol {
+            size += 32;
+        }
+        size
+    }
+}
+
+fn entropy_bits(pw: &str) -> f64 {
+    let len = pw.chars().count() as f64;
+    let alphabet = Classes::of(pw).alphabet_size() as f64;
+
+    if len == 0.0 || alphabet <= 1.0 {
+        return 0.0;
+    }
+
+    len * alphabet.log2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_length_rejects_short_passwords() {
+        assert!(min_length(8)("short").is_err());
+        assert!(min_length(8)("long enough").is_ok());
+        assert!(min_length(8)("exactly8").is_ok());
+    }
+
+    #[test]
+    fn min_character_classes_counts_distinct_classes() {
+        assert!(min_character_classes(3)("abc").is_err());
+        assert!(min_character_classes(3)("abcABC").is_err());
+        assert!(min_character_classes(3)("abcABC123").is_ok());
+        assert!(min_character_classes(4)("abcABC123!").is_ok());
+    }
+
+    #[test]
+    fn classes_of_detects_each_class() {
+        let classes = Classes::of("aB3!");
+        assert!(classes.lower);
+        assert!(classes.upper);
+        assert!(classes.digit);
+        assert!(classes.symbol);
+    }
+
+    #[test]
+    fn alphabet_size_sums_present_classes() {
+        assert_eq!(Classes::of("abc").alphabet_size(), 26);
+        assert_eq!(Classes::of("abcABC").alphabet_size(), 52);
+        assert_eq!(Classes::of("abc123").alphabet_size(), 36);
+        assert_eq!(Classes::of("abcABC123!").alphabet_size(), 94);
+        assert_eq!(Classes::of("").alphabet_size(), 0);
+    }
+
+    #[test]
+    fn entropy_bits_is_zero_for_empty_password() {
+        assert_eq!(entropy_bits(""), 0.0);
+    }
+
+    #[test]
+    fn entropy_bits_matches_length_times_log2_alphabet() {
+        // 4 lowercase letters: log2(26) * 4
+        let expected = 4.0 * 26_f64.log2();
+        assert!((entropy_bits("abcd") - expected).abs() < 1e-9);
+    }
+}